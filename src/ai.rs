@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rayon::prelude::*;
+
+use crate::bitboard::Bitboard;
+use crate::{Board, Move, Piece};
+
+const WIN_SCORE: i32 = 1_000_000;
+
+#[derive(Clone, Copy)]
+enum Flag {
+    Exact,
+    Lower,
+    Upper,
+}
+
+struct TtEntry {
+    depth: u32,
+    value: i32,
+    flag: Flag,
+    best: Move,
+}
+
+// Shared across the whole search tree, including the parallel root, so
+// transpositions found down one branch speed up the others.
+type Tt = Mutex<HashMap<u64, TtEntry>>;
+
+/// Searches `depth` plies with negamax alpha-beta and returns the best move
+/// for the side to move, or `None` if that side has no legal moves.
+///
+/// The root is parallelized with Rayon: each top-level move gets its own
+/// bitboard clone and its own alpha-beta window, searched concurrently, then
+/// reduced to the best score.
+pub(crate) fn best_move(board: &Board, depth: u32) -> Option<Move> {
+    let bitboard = Bitboard::from_board(board);
+    let moves = order_moves(bitboard.legal_moves(), None);
+    if moves.is_empty() {
+        return None;
+    }
+
+    let tt: Tt = Mutex::new(HashMap::new());
+
+    moves
+        .into_par_iter()
+        .map(|mv| {
+            let mut child = bitboard.clone();
+            child.do_move(&mv);
+            let score = -negamax(&child, depth.saturating_sub(1), -WIN_SCORE, WIN_SCORE, &tt);
+            (mv, score)
+        })
+        .max_by_key(|(_mv, score)| *score)
+        .map(|(mv, _score)| mv)
+}
+
+fn negamax(board: &Bitboard, depth: u32, mut alpha: i32, mut beta: i32, tt: &Tt) -> i32 {
+    if depth == 0 || board.is_over() {
+        return evaluate(board);
+    }
+
+    let hash = board.hash();
+    let mut tt_move = None;
+    {
+        let table = tt.lock().unwrap();
+        if let Some(entry) = table.get(&hash) {
+            tt_move = Some(entry.best.clone());
+            if entry.depth >= depth {
+                match entry.flag {
+                    Flag::Exact => return entry.value,
+                    Flag::Lower => alpha = alpha.max(entry.value),
+                    Flag::Upper => beta = beta.min(entry.value),
+                }
+                if alpha >= beta {
+                    return entry.value;
+                }
+            }
+        }
+    }
+
+    // Re-validate the stored move against the actual legal moves here, in
+    // case a hash collision paired this position with someone else's entry.
+    let moves = order_moves(board.legal_moves(), tt_move);
+    if moves.is_empty() {
+        // No legal moves: this side passes and the opponent moves instead,
+        // same as `Bitboard::pass`.
+        let mut passed = board.clone();
+        passed.pass();
+        return -negamax(&passed, depth - 1, -beta, -alpha, tt);
+    }
+
+    let original_alpha = alpha;
+    let mut best = -WIN_SCORE;
+    let mut best_move = moves[0].clone();
+    for mv in moves {
+        let mut child = board.clone();
+        child.do_move(&mv);
+        let score = -negamax(&child, depth - 1, -beta, -alpha, tt);
+        if score > best {
+            best = score;
+            best_move = mv;
+        }
+        if best > alpha {
+            alpha = best;
+        }
+        if alpha >= beta {
+            break; // Beta cutoff: the opponent won't let this line happen.
+        }
+    }
+
+    let flag = if best <= original_alpha {
+        Flag::Upper
+    } else if best >= beta {
+        Flag::Lower
+    } else {
+        Flag::Exact
+    };
+    tt.lock().unwrap().insert(
+        hash,
+        TtEntry {
+            depth,
+            value: best,
+            flag,
+            best: best_move,
+        },
+    );
+
+    best
+}
+
+// Leaps don't grow the mover's total the way steps do, so trying steps first
+// tends to find the strongest reply sooner and prune more of the tree. The
+// transposition table's remembered best move, if still legal here, goes first.
+fn order_moves(moves: Vec<Move>, tt_move: Option<Move>) -> Vec<Move> {
+    let mut rest = moves;
+    let mut ordered = Vec::with_capacity(rest.len());
+    if let Some(tt_move) = tt_move {
+        if let Some(pos) = rest.iter().position(|mv| *mv == tt_move) {
+            ordered.push(rest.remove(pos));
+        }
+    }
+    rest.sort_by_key(|mv| matches!(mv, Move::Leap(_, _)));
+    ordered.extend(rest);
+    ordered
+}
+
+fn evaluate(board: &Bitboard) -> i32 {
+    if board.is_over() {
+        return match board.winner() {
+            Some(winner) if winner == *board.turn() => WIN_SCORE,
+            Some(_) => -WIN_SCORE,
+            None => 0,
+        };
+    }
+    let (black, white) = board.score();
+    let diff = black as i32 - white as i32;
+    match board.turn() {
+        Piece::Black => diff,
+        Piece::White => -diff,
+        Piece::Empty => 0,
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::{Board, Dir, Move, Piece, Pos, LEAP_DIRECTIONS, STEP_DIRECTIONS};
+
+const ZOBRIST_CELLS: usize = 128;
+
+struct ZobristKeys {
+    black: [u64; ZOBRIST_CELLS],
+    white: [u64; ZOBRIST_CELLS],
+    turn: u64,
+}
+
+static ZOBRIST: OnceLock<ZobristKeys> = OnceLock::new();
+
+// A fixed table of keys, generated once at first use and reused for the
+// rest of the run (not reseeded per search, so transposition hashes from
+// different positions stay comparable).
+fn zobrist() -> &'static ZobristKeys {
+    ZOBRIST.get_or_init(|| {
+        let mut seed = 0x9E3779B97F4A7C15u64;
+        let mut next_key = || {
+            seed = splitmix64(seed);
+            seed
+        };
+        let mut black = [0u64; ZOBRIST_CELLS];
+        let mut white = [0u64; ZOBRIST_CELLS];
+        for i in 0..ZOBRIST_CELLS {
+            black[i] = next_key();
+            white[i] = next_key();
+        }
+        let turn = next_key();
+        ZobristKeys { black, white, turn }
+    })
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// The cell layout and precomputed neighbor masks are fixed for the life of
+// a search tree, so they're shared behind an `Arc` instead of living inline
+// on `Bitboard`: cloning a node for a child move only copies the occupancy
+// words, not these tables.
+struct Tables {
+    pos_of: Vec<Pos>,
+    index_of: HashMap<Pos, usize>,
+    step_mask: Vec<u128>,
+    leap_mask: Vec<u128>,
+}
+
+/// A compact occupancy representation of a `Board`, used by the search so
+/// that move generation is a handful of bit operations instead of HashMap
+/// lookups. The board's cells are assigned dense indices 0..N once at
+/// construction, and occupancy becomes two `u128` bitboards. `Board` stays
+/// the human-readable façade for loading and display; `Bitboard` is derived
+/// from it for anything performance-sensitive.
+#[derive(Clone)]
+pub(crate) struct Bitboard {
+    tables: Arc<Tables>,
+    valid: u128,
+    black: u128,
+    white: u128,
+    turn: Piece,
+    hash: u64,
+}
+
+impl Bitboard {
+    pub(crate) fn from_board(board: &Board) -> Bitboard {
+        let mut pos_of: Vec<Pos> = board.cells().copied().collect();
+        pos_of.sort_by_key(|&(x, y)| (y, x));
+
+        let index_of: HashMap<Pos, usize> = pos_of
+            .iter()
+            .enumerate()
+            .map(|(i, &pos)| (pos, i))
+            .collect();
+
+        let valid = bit_range(pos_of.len());
+        let step_mask = pos_of
+            .iter()
+            .map(|&pos| neighbor_mask(pos, STEP_DIRECTIONS, &index_of))
+            .collect();
+        let leap_mask = pos_of
+            .iter()
+            .map(|&pos| neighbor_mask(pos, LEAP_DIRECTIONS, &index_of))
+            .collect();
+
+        let mut black = 0u128;
+        let mut white = 0u128;
+        let mut hash = 0u64;
+        for (i, &pos) in pos_of.iter().enumerate() {
+            match board.at(&pos) {
+                Some(Piece::Black) => {
+                    black |= 1 << i;
+                    hash ^= zobrist().black[i];
+                }
+                Some(Piece::White) => {
+                    white |= 1 << i;
+                    hash ^= zobrist().white[i];
+                }
+                _ => {}
+            }
+        }
+        if *board.turn() == Piece::White {
+            hash ^= zobrist().turn;
+        }
+
+        Bitboard {
+            tables: Arc::new(Tables {
+                pos_of,
+                index_of,
+                step_mask,
+                leap_mask,
+            }),
+            valid,
+            black,
+            white,
+            turn: board.turn().clone(),
+            hash,
+        }
+    }
+
+    pub(crate) fn turn(&self) -> &Piece {
+        &self.turn
+    }
+
+    pub(crate) fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    fn zobrist_key(&self, i: usize, piece: &Piece) -> u64 {
+        match piece {
+            Piece::Black => zobrist().black[i],
+            Piece::White => zobrist().white[i],
+            Piece::Empty => 0,
+        }
+    }
+
+    // Toggles the side to move without touching occupancy. The turn key
+    // must flip here too, same as in `do_move`, or a passed position and
+    // its non-passed twin would hash to the same transposition table entry.
+    pub(crate) fn pass(&mut self) {
+        self.turn = self.turn.opposite();
+        self.hash ^= zobrist().turn;
+    }
+
+    pub(crate) fn do_move(&mut self, mv: &Move) {
+        let mover = self.turn.clone();
+        let dest = match mv {
+            Move::Step(dest) => self.tables.index_of[dest],
+            Move::Leap(src, dest) => {
+                let src = self.tables.index_of[src];
+                self.clear_bit(src);
+                self.hash ^= self.zobrist_key(src, &mover);
+                self.tables.index_of[dest]
+            }
+        };
+        self.set_bit(dest);
+        self.hash ^= self.zobrist_key(dest, &mover);
+        self.flip_neighbors(dest);
+        self.hash ^= zobrist().turn;
+        self.turn = self.turn.opposite();
+    }
+
+    fn set_bit(&mut self, i: usize) {
+        match self.turn {
+            Piece::Black => self.black |= 1 << i,
+            Piece::White => self.white |= 1 << i,
+            Piece::Empty => {}
+        }
+    }
+
+    fn clear_bit(&mut self, i: usize) {
+        let keep = !(1u128 << i);
+        self.black &= keep;
+        self.white &= keep;
+    }
+
+    fn flip_neighbors(&mut self, i: usize) {
+        let (mine, enemy) = match self.turn {
+            Piece::Black => (self.black, self.white),
+            Piece::White => (self.white, self.black),
+            Piece::Empty => return,
+        };
+        let captured = self.tables.step_mask[i] & enemy;
+        let enemy_piece = self.turn.opposite();
+        for c in set_bits(captured) {
+            self.hash ^= self.zobrist_key(c, &enemy_piece);
+            self.hash ^= self.zobrist_key(c, &self.turn);
+        }
+        let mine = mine | captured;
+        let enemy = enemy & !captured;
+        match self.turn {
+            Piece::Black => {
+                self.black = mine;
+                self.white = enemy;
+            }
+            Piece::White => {
+                self.white = mine;
+                self.black = enemy;
+            }
+            Piece::Empty => {}
+        }
+    }
+
+    pub(crate) fn legal_moves(&self) -> Vec<Move> {
+        let empty = self.valid & !self.black & !self.white;
+        let mine = match self.turn {
+            Piece::Black => self.black,
+            Piece::White => self.white,
+            Piece::Empty => 0,
+        };
+
+        let mut moves = Vec::new();
+        let mut step_dests = 0u128;
+        for i in set_bits(mine) {
+            let steps = self.tables.step_mask[i] & empty;
+            for d in set_bits(steps & !step_dests) {
+                moves.push(Move::Step(self.tables.pos_of[d]));
+            }
+            step_dests |= steps;
+
+            let leaps = self.tables.leap_mask[i] & empty;
+            for d in set_bits(leaps) {
+                moves.push(Move::Leap(self.tables.pos_of[i], self.tables.pos_of[d]));
+            }
+        }
+        moves
+    }
+
+    pub(crate) fn must_pass(&self) -> bool {
+        self.legal_moves().is_empty()
+    }
+
+    pub(crate) fn is_over(&self) -> bool {
+        let empty = self.valid & !self.black & !self.white;
+        if empty == 0 {
+            return true;
+        }
+        if !self.must_pass() {
+            return false;
+        }
+        let mut other = self.clone();
+        other.turn = other.turn.opposite();
+        other.must_pass()
+    }
+
+    pub(crate) fn score(&self) -> (usize, usize) {
+        (self.black.count_ones() as usize, self.white.count_ones() as usize)
+    }
+
+    // Standard Ataxx endgame rule: a side eliminated entirely hands every
+    // remaining empty cell to its opponent before the final count is taken.
+    fn fill_for_endgame(&mut self) {
+        let (black, white) = self.score();
+        if black == 0 {
+            self.white = self.valid;
+        } else if white == 0 {
+            self.black = self.valid;
+        }
+    }
+
+    pub(crate) fn winner(&self) -> Option<Piece> {
+        let mut filled = self.clone();
+        filled.fill_for_endgame();
+        let (black, white) = filled.score();
+        if black > white {
+            Some(Piece::Black)
+        } else if white > black {
+            Some(Piece::White)
+        } else {
+            None
+        }
+    }
+}
+
+fn bit_range(count: usize) -> u128 {
+    if count >= 128 {
+        u128::MAX
+    } else {
+        (1u128 << count) - 1
+    }
+}
+
+fn neighbor_mask(pos: Pos, directions: &[Dir], index_of: &HashMap<Pos, usize>) -> u128 {
+    let mut mask = 0u128;
+    for &(dx, dy) in directions {
+        let neighbor = (pos.0 + dx, pos.1 + dy);
+        if let Some(&i) = index_of.get(&neighbor) {
+            mask |= 1 << i;
+        }
+    }
+    mask
+}
+
+fn set_bits(mut mask: u128) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            None
+        } else {
+            let i = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            Some(i)
+        }
+    })
+}
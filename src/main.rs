@@ -1,15 +1,18 @@
-use std::collections::{hash_map::Entry, HashMap};
+mod ai;
+mod bitboard;
+
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::fmt;
 
 #[derive(PartialEq, Clone, Debug)]
-enum Piece {
+pub(crate) enum Piece {
     Empty,
     Black,
     White,
 }
 
 impl Piece {
-    fn opposite(&self) -> Piece {
+    pub(crate) fn opposite(&self) -> Piece {
         match self {
             Piece::Empty => Piece::Empty,
             Piece::Black => Piece::White,
@@ -18,8 +21,8 @@ impl Piece {
     }
 }
 
-type Pos = (i32, i32);
-type Dir = (i32, i32);
+pub(crate) type Pos = (i32, i32);
+pub(crate) type Dir = (i32, i32);
 
 // Double-width horizontal layout. (https://www.redblobgames.com/grids/hexagons/)
 // 0 is the piece position, 1s are one step away, and 2s are one leap away.
@@ -29,7 +32,7 @@ type Dir = (i32, i32);
 //  2 1 1 2
 //   2 2 2
 
-const STEP_DIRECTIONS: &'static [Dir] = &[
+pub(crate) const STEP_DIRECTIONS: &'static [Dir] = &[
     (2, 0),   // E
     (1, 1),   // NE
     (-1, 1),  // NW
@@ -37,7 +40,7 @@ const STEP_DIRECTIONS: &'static [Dir] = &[
     (-1, -1), // SW
     (1, -1),  // SE
 ];
-const LEAP_DIRECTIONS: &'static [Dir] = &[
+pub(crate) const LEAP_DIRECTIONS: &'static [Dir] = &[
     (4, 0),   // E
     (3, 1),   // ENE
     (2, 2),   // NE
@@ -52,13 +55,40 @@ const LEAP_DIRECTIONS: &'static [Dir] = &[
     (3, -1),  // ESE
 ];
 
-enum Move {
+#[derive(Clone, PartialEq)]
+pub(crate) enum Move {
     Step(Pos),
     Leap(Pos, Pos),
 }
 
+impl Move {
+    pub(crate) fn to_algebraic(&self) -> String {
+        match self {
+            Move::Step(dest) => cell_name(*dest),
+            Move::Leap(src, dest) => format!("{}-{}", cell_name(*src), cell_name(*dest)),
+        }
+    }
+}
+
+// The only starting layout this game is played on, kept as a constant so
+// notation parsing can rebuild the board's fixed shape from it; each
+// `from_notation` call loads this diagram afresh and then overwrites every
+// cell from the notation.
+const STARTING_POSITION: &str = "
+    X - - - O
+   - - - - - -
+  - - - - - - -
+ - - - -   - - -
+O - -   - - - - X
+ - - - -   - - -
+  - - - - - - -
+   - - - - - -
+    X - - - O
+";
+
 type PositionMap = HashMap<Pos, Piece>;
-struct Board {
+#[derive(Clone)]
+pub(crate) struct Board {
     positions: PositionMap,
     turn: Piece,
 }
@@ -103,10 +133,15 @@ impl Board {
         Ok(board)
     }
 
-    fn at(&self, pos: &Pos) -> Option<&Piece> {
+    pub(crate) fn at(&self, pos: &Pos) -> Option<&Piece> {
         self.positions.get(pos)
     }
 
+    // All cells that exist on the board, regardless of occupancy.
+    pub(crate) fn cells(&self) -> impl Iterator<Item = &Pos> {
+        self.positions.keys()
+    }
+
     fn set(&mut self, pos: &Pos, piece: Piece) {
         if let Entry::Occupied(mut entry) = self.positions.entry(*pos) {
             entry.insert(piece);
@@ -174,7 +209,17 @@ impl Board {
         self._neighbors(pos, LEAP_DIRECTIONS)
     }
 
-    fn do_move(&mut self, mv: &Move) {
+    pub(crate) fn turn(&self) -> &Piece {
+        &self.turn
+    }
+
+    // Toggles the side to move. Callers are responsible for only passing
+    // when `legal_moves()` is empty.
+    pub(crate) fn pass(&mut self) {
+        self.turn = self.turn.opposite();
+    }
+
+    pub(crate) fn do_move(&mut self, mv: &Move) {
         match mv {
             Move::Step(pos) => {
                 self.set(pos, self.turn.clone());
@@ -198,6 +243,151 @@ impl Board {
             }
         }
     }
+
+    pub(crate) fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let mut step_dests: HashSet<Pos> = HashSet::new();
+        let pieces: Vec<Pos> = self
+            .positions
+            .iter()
+            .filter(|(_pos, piece)| **piece == self.turn)
+            .map(|(pos, _piece)| *pos)
+            .collect();
+        for pos in pieces {
+            for dest in self.step_neighbors(&pos) {
+                if self.at(&dest) == Some(&Piece::Empty) && step_dests.insert(dest) {
+                    moves.push(Move::Step(dest));
+                }
+            }
+            for dest in self.leap_neighbors(&pos) {
+                if self.at(&dest) == Some(&Piece::Empty) {
+                    moves.push(Move::Leap(pos, dest));
+                }
+            }
+        }
+        moves
+    }
+
+    // `Bitboard` is the one place game-over detection, scoring, and the
+    // endgame fill rule are implemented; `Board` just asks it.
+    pub(crate) fn is_over(&self) -> bool {
+        bitboard::Bitboard::from_board(self).is_over()
+    }
+
+    pub(crate) fn score(&self) -> (usize, usize) {
+        bitboard::Bitboard::from_board(self).score()
+    }
+
+    pub(crate) fn winner(&self) -> Option<Piece> {
+        bitboard::Bitboard::from_board(self).winner()
+    }
+
+    // The fixed cell order notation is serialized and parsed over: row by
+    // row, left to right, matching the order the starting diagram is read.
+    fn cells_in_notation_order(&self) -> Vec<Pos> {
+        let mut cells: Vec<Pos> = self.cells().copied().collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        cells
+    }
+
+    pub(crate) fn to_notation(&self) -> String {
+        let mut notation = String::new();
+        let mut empty_run = 0;
+        for pos in self.cells_in_notation_order() {
+            match self.at(&pos) {
+                Some(Piece::Empty) => empty_run += 1,
+                Some(piece) => {
+                    if empty_run > 0 {
+                        notation.push_str(&empty_run.to_string());
+                        empty_run = 0;
+                    }
+                    notation.push(match piece {
+                        Piece::Black => 'X',
+                        Piece::White => 'O',
+                        Piece::Empty => unreachable!(),
+                    });
+                }
+                None => unreachable!("cells_in_notation_order only yields real cells"),
+            }
+        }
+        if empty_run > 0 {
+            notation.push_str(&empty_run.to_string());
+        }
+        notation.push(' ');
+        notation.push(match self.turn {
+            Piece::Black => 'X',
+            Piece::White => 'O',
+            Piece::Empty => unreachable!(),
+        });
+        notation
+    }
+
+    pub(crate) fn from_notation(notation: &str) -> Result<Board, String> {
+        let mut fields = notation.split_whitespace();
+        let layout = fields.next().ok_or("Missing board field.")?;
+        let turn = fields.next().ok_or("Missing side-to-move field.")?;
+
+        let mut board = Board::load(STARTING_POSITION)?;
+        let mut cells = board.cells_in_notation_order().into_iter();
+
+        let mut chars = layout.chars().peekable();
+        while let Some(c) = chars.next() {
+            if let Some(digit) = c.to_digit(10) {
+                let mut run = digit as usize;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    run = run * 10 + digit as usize;
+                    chars.next();
+                }
+                for _ in 0..run {
+                    let pos = cells.next().ok_or("Notation has too many cells.")?;
+                    board.set(&pos, Piece::Empty);
+                }
+            } else {
+                let piece = match c {
+                    'X' => Piece::Black,
+                    'O' => Piece::White,
+                    _ => return Err(format!("Unexpected character '{}' in notation.", c)),
+                };
+                let pos = cells.next().ok_or("Notation has too many cells.")?;
+                board.set(&pos, piece);
+            }
+        }
+        if cells.next().is_some() {
+            return Err("Notation has too few cells.".into());
+        }
+
+        board.turn = match turn {
+            "X" => Piece::Black,
+            "O" => Piece::White,
+            _ => return Err(format!("Unexpected side to move '{}'.", turn)),
+        };
+
+        Ok(board)
+    }
+
+    pub(crate) fn parse_move(&self, notation: &str) -> Result<Move, String> {
+        let mv = match notation.split_once('-') {
+            Some((src, dest)) => Move::Leap(parse_cell(src)?, parse_cell(dest)?),
+            None => Move::Step(parse_cell(notation)?),
+        };
+
+        if self.legal_moves().contains(&mv) {
+            Ok(mv)
+        } else {
+            Err(format!("'{}' is not a legal move.", notation))
+        }
+    }
+
+    // Parses and applies a notation position plus a whitespace-separated
+    // list of algebraic moves, e.g. to replay a saved game from a fixture.
+    pub(crate) fn replay(notation: &str, moves: &str) -> Result<Board, String> {
+        let mut board = Board::from_notation(notation)?;
+        for mv in moves.split_whitespace() {
+            let mv = board.parse_move(mv)?;
+            board.do_move(&mv);
+        }
+        Ok(board)
+    }
 }
 
 impl fmt::Display for Board {
@@ -223,27 +413,73 @@ fn offset((x, y): Pos, (dx, dy): Dir) -> Pos {
     (x + dx, y + dy)
 }
 
+// Double-width x coordinates always share parity with y, so a column letter
+// plus a row number names a cell uniquely: "f4" is column 'f' (x = 2*5 + y%2)
+// on row 4 (y = 3).
+fn cell_name((x, y): Pos) -> String {
+    let letter = (b'a' + (x / 2) as u8) as char;
+    format!("{}{}", letter, y + 1)
+}
+
+fn parse_cell(name: &str) -> Result<Pos, String> {
+    let mut chars = name.chars();
+    let letter = chars
+        .next()
+        .filter(|c| c.is_ascii_lowercase())
+        .ok_or_else(|| format!("Invalid cell name '{}'.", name))?;
+    let row: i32 = chars
+        .as_str()
+        .parse()
+        .map_err(|_| format!("Invalid cell name '{}'.", name))?;
+
+    let col = (letter as u8 - b'a') as i32;
+    let y = row - 1;
+    let x = 2 * col + y.rem_euclid(2);
+    Ok((x, y))
+}
+
 fn main() {
-    let mut board = Board::load(
-        "
-            X - - - O
-           - - - - - -
-          - - - - - - -
-         - - - -   - - -
-        O - -   - - - - X
-         - - - -   - - -
-          - - - - - - -
-           - - - - - -
-            X - - - O
-        ",
-    )
-    .expect("board error");
-
-    println!("{}", board.to_string());
+    let depth: u32 = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(4);
+
+    let mut board = Board::load(STARTING_POSITION).expect("board error");
+
+    println!("{}", board);
     board.do_move(&Move::Step((6, 0)));
-    println!("{}", board.to_string());
+    println!("{}", board);
     board.do_move(&Move::Leap((12, 0), (8, 0)));
-    println!("{}", board.to_string());
+    println!("{}", board);
     board.do_move(&Move::Leap((4, 0), (7, 1)));
-    println!("{}", board.to_string());
+    println!("{}", board);
+
+    // Let the engine play out the rest of the game against itself.
+    while !board.is_over() {
+        match ai::best_move(&board, depth) {
+            Some(mv) => {
+                println!("{:?} plays {}", board.turn(), mv.to_algebraic());
+                board.do_move(&mv);
+            }
+            None => {
+                println!("{:?} has no moves and passes.", board.turn());
+                board.pass();
+            }
+        }
+        println!("{}", board);
+    }
+
+    let (black, white) = board.score();
+    println!("Game over. Score: black {}, white {}", black, white);
+    match board.winner() {
+        Some(winner) => println!("{:?} wins.", winner),
+        None => println!("Draw."),
+    }
+    println!("Final position: {}", board.to_notation());
+
+    let starting_notation = Board::load(STARTING_POSITION)
+        .expect("board error")
+        .to_notation();
+    let opening = Board::replay(&starting_notation, "d1 g1-e1 c1-d2").expect("replay error");
+    println!("Replayed opening:\n{}", opening);
 }